@@ -1,32 +1,127 @@
 // csv_loader.rs
-// Handles loading and parsing the CO2 emissions CSV dataset.
+// Handles loading and parsing the CO2 emissions CSV dataset, tolerating malformed or
+// partially-dirty rows instead of aborting the whole run on the first bad one.
 
 use std::collections::HashMap;
-use serde::Deserialize;
+use std::fmt;
 
-// Represents a single record from the CO2 dataset.
-#[derive(Debug, Deserialize)]
-pub struct Record {
+// Which CSV columns to read: which holds the country name, which the year, and which
+// numeric column to use as the emissions metric. The real OWID file has dozens of
+// columns, so callers may want `co2` or `methane_per_capita` instead of the default.
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
     pub country: String,
-    pub year: i32,
-    pub co2_per_capita: Option<f64>,
+    pub year: String,
+    pub metric: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        ColumnMapping {
+            country: "country".to_string(),
+            year: "year".to_string(),
+            metric: "co2_per_capita".to_string(),
+        }
+    }
+}
+
+// Fatal error opening or reading the CSV file itself (bad path, corrupt header row,
+// etc.). Individual malformed rows are not fatal -- see `load_data`.
+#[derive(Debug)]
+pub struct LoadError(csv::Error);
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to read CSV file: {}", self.0)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<csv::Error> for LoadError {
+    fn from(err: csv::Error) -> Self {
+        LoadError(err)
+    }
 }
 
 // Loads and parses the dataset from a given CSV file path.
-// Inputs: Path to the CSV file
-// Outputs:  HashMap of country -> vector of (year, co2_per_capita) pairs
-pub fn load_data(path: &str) -> HashMap<String, Vec<(i32, f64)>> {
-    let mut rdr = csv::Reader::from_path(path).expect("Failed to open CSV file.");
+// Inputs:
+//  - path to the CSV file
+//  - columns: which CSV columns hold the country/year/metric values
+//  - year_range: optional inclusive (min, max) year filter applied during load
+// Outputs: HashMap of country -> vector of (year, metric) pairs, or a LoadError if the
+// file itself could not be opened or its header row read. Rows with a missing/unparseable
+// country or year, or an unparseable metric, are skipped and reported rather than
+// aborting the load; rows with no metric value at all (common in the real dataset) are
+// silently skipped, matching the old `Option<f64>` behavior.
+pub fn load_data(
+    path: &str,
+    columns: &ColumnMapping,
+    year_range: Option<(i32, i32)>,
+) -> Result<HashMap<String, Vec<(i32, f64)>>, LoadError> {
+    let mut rdr = csv::Reader::from_path(path)?;
+    let headers = rdr.headers()?.clone();
+    let col_index = |name: &str| headers.iter().position(|h| h == name);
+
+    let (country_idx, year_idx, metric_idx) =
+        match (col_index(&columns.country), col_index(&columns.year), col_index(&columns.metric)) {
+            (Some(c), Some(y), Some(m)) => (c, y, m),
+            _ => {
+                println!(
+                    "⚠️  Column(s) '{}', '{}', or '{}' not found in '{}'; no rows loaded.",
+                    columns.country, columns.year, columns.metric, path
+                );
+                return Ok(HashMap::new());
+            }
+        };
+
     let mut data: HashMap<String, Vec<(i32, f64)>> = HashMap::new();
+    let mut skipped: Vec<(usize, String)> = Vec::new();
+
+    for (row_num, result) in rdr.records().enumerate() {
+        let line = row_num + 2; // +1 for 1-indexing, +1 for the header row
+
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                skipped.push((line, format!("could not read row: {}", e)));
+                continue;
+            }
+        };
+
+        let country = match record.get(country_idx) {
+            Some(c) if !c.is_empty() => c.to_string(),
+            _ => {
+                skipped.push((line, format!("missing '{}' column", columns.country)));
+                continue;
+            }
+        };
+
+        let year: i32 = match record.get(year_idx).map(str::parse) {
+            Some(Ok(y)) => y,
+            _ => {
+                skipped.push((line, format!("missing or unparseable '{}' column", columns.year)));
+                continue;
+            }
+        };
 
-    for result in rdr.deserialize() {
-        let record: Record = result.expect("Failed to deserialize CSV record.");
-        if let Some(co2) = record.co2_per_capita {
-            // Group by country name
-            data.entry(record.country.clone())
-                .or_default()
-                .push((record.year, co2));
+        if let Some((min_year, max_year)) = year_range {
+            if year < min_year || year > max_year {
+                continue;
+            }
         }
+
+        let metric_str = record.get(metric_idx).filter(|s| !s.is_empty());
+        let metric: f64 = match metric_str.map(str::parse) {
+            Some(Ok(v)) => v,
+            Some(Err(_)) => {
+                skipped.push((line, format!("unparseable '{}' value", columns.metric)));
+                continue;
+            }
+            None => continue, // no metric recorded for this row -- not an error
+        };
+
+        data.entry(country).or_default().push((year, metric));
     }
 
     // Sort the emissions data chronologically for each country
@@ -34,5 +129,98 @@ pub fn load_data(path: &str) -> HashMap<String, Vec<(i32, f64)>> {
         values.sort_by_key(|k| k.0);
     }
 
-    data
+    if !skipped.is_empty() {
+        println!("⚠️  Skipped {} malformed row(s) while loading '{}':", skipped.len(), path);
+        for (line, reason) in &skipped {
+            println!("  - line {}: {}", line, reason);
+        }
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // Writes `contents` to a uniquely-named temp file and returns its path.
+    fn write_temp_csv(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, contents).expect("Failed to write temp CSV file.");
+        path
+    }
+
+    #[test]
+    fn test_load_data_skips_malformed_rows_and_keeps_good_ones() {
+        let path = write_temp_csv(
+            "co2_cluster_analysis_test_malformed.csv",
+            "country,year,co2_per_capita\n\
+             Testland,2000,5.5\n\
+             ,2001,6.0\n\
+             Testland,not_a_year,7.0\n\
+             Testland,2002,not_a_number\n\
+             Testland,2003,\n",
+        );
+
+        let data = load_data(path.to_str().unwrap(), &ColumnMapping::default(), None).unwrap();
+        fs::remove_file(&path).ok();
+
+        let values = data.get("Testland").expect("Expected Testland to be loaded.");
+        assert_eq!(values, &vec![(2000, 5.5)]);
+    }
+
+    #[test]
+    fn test_load_data_applies_year_range_filter() {
+        let path = write_temp_csv(
+            "co2_cluster_analysis_test_year_range.csv",
+            "country,year,co2_per_capita\n\
+             Testland,1990,1.0\n\
+             Testland,2000,2.0\n\
+             Testland,2010,3.0\n",
+        );
+
+        let data = load_data(path.to_str().unwrap(), &ColumnMapping::default(), Some((1995, 2005))).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(data.get("Testland").unwrap(), &vec![(2000, 2.0)]);
+    }
+
+    #[test]
+    fn test_load_data_honors_custom_column_mapping() {
+        let path = write_temp_csv(
+            "co2_cluster_analysis_test_columns.csv",
+            "nation,yr,methane_per_capita\n\
+             Testland,2000,0.42\n",
+        );
+
+        let columns = ColumnMapping {
+            country: "nation".to_string(),
+            year: "yr".to_string(),
+            metric: "methane_per_capita".to_string(),
+        };
+        let data = load_data(path.to_str().unwrap(), &columns, None).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(data.get("Testland").unwrap(), &vec![(2000, 0.42)]);
+    }
+
+    #[test]
+    fn test_load_data_missing_column_returns_empty_map_not_error() {
+        let path = write_temp_csv(
+            "co2_cluster_analysis_test_missing_column.csv",
+            "country,year,some_other_metric\nTestland,2000,1.0\n",
+        );
+
+        let data = load_data(path.to_str().unwrap(), &ColumnMapping::default(), None).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_load_data_missing_file_returns_error() {
+        let result = load_data("/nonexistent/path/does_not_exist.csv", &ColumnMapping::default(), None);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,173 @@
+// export.rs
+// Serializes the similarity graph into formats external graph tools (Gephi, Cytoscape,
+// igraph) can load directly, instead of the plain-text cluster listing in graph.rs.
+
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Writes the graph as GraphML: node labels as country names, edges carrying the cosine
+// similarity as a typed `double` weight attribute, and (when a clustering is supplied) a
+// per-node `cluster` attribute so the partition can be colored downstream.
+// Inputs:
+//  - reference to the similarity graph
+//  - output path
+//  - optional per-node cluster ids (e.g. from `graph::louvain_clusters`)
+// Outputs: GraphML file written to disk
+pub fn export_graphml(graph: &Graph<String, f64>, path: &str, clusters: Option<&HashMap<NodeIndex, usize>>) {
+    let file = File::create(path).expect("Failed to create GraphML output file.");
+    let mut writer = BufWriter::new(file);
+    let err = "Failed to write GraphML output.";
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#).expect(err);
+    writeln!(writer, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#).expect(err);
+    writeln!(writer, r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#).expect(err);
+    writeln!(writer, r#"  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>"#).expect(err);
+    if clusters.is_some() {
+        writeln!(writer, r#"  <key id="cluster" for="node" attr.name="cluster" attr.type="int"/>"#).expect(err);
+    }
+    writeln!(writer, r#"  <graph id="G" edgedefault="undirected">"#).expect(err);
+
+    for node_idx in graph.node_indices() {
+        writeln!(writer, r#"    <node id="n{}">"#, node_idx.index()).expect(err);
+        writeln!(writer, r#"      <data key="label">{}</data>"#, escape_xml(&graph[node_idx])).expect(err);
+        if let Some(clusters) = clusters {
+            writeln!(writer, r#"      <data key="cluster">{}</data>"#, clusters[&node_idx]).expect(err);
+        }
+        writeln!(writer, "    </node>").expect(err);
+    }
+
+    for (i, edge) in graph.edge_references().enumerate() {
+        writeln!(
+            writer,
+            r#"    <edge id="e{}" source="n{}" target="n{}">"#,
+            i,
+            edge.source().index(),
+            edge.target().index()
+        )
+        .expect(err);
+        writeln!(writer, r#"      <data key="weight">{}</data>"#, edge.weight()).expect(err);
+        writeln!(writer, "    </edge>").expect(err);
+    }
+
+    writeln!(writer, "  </graph>").expect(err);
+    writeln!(writer, "</graphml>").expect(err);
+
+    println!("✅  Graph exported to '{}' (GraphML)", path);
+}
+
+// Writes the graph as Graphviz DOT: a `graph { ... }` block with `label=` and `weight=`
+// on every edge, plus a `cluster=` node attribute when a partition is supplied.
+// Inputs:
+//  - reference to the similarity graph
+//  - output path
+//  - optional per-node cluster ids
+// Outputs: DOT file written to disk
+pub fn export_dot(graph: &Graph<String, f64>, path: &str, clusters: Option<&HashMap<NodeIndex, usize>>) {
+    let file = File::create(path).expect("Failed to create DOT output file.");
+    let mut writer = BufWriter::new(file);
+    let err = "Failed to write DOT output.";
+
+    writeln!(writer, "graph {{").expect(err);
+
+    for node_idx in graph.node_indices() {
+        let label = escape_dot(&graph[node_idx]);
+        match clusters {
+            Some(clusters) => writeln!(
+                writer,
+                r#"  n{} [label="{}", cluster={}];"#,
+                node_idx.index(),
+                label,
+                clusters[&node_idx]
+            )
+            .expect(err),
+            None => writeln!(writer, r#"  n{} [label="{}"];"#, node_idx.index(), label).expect(err),
+        }
+    }
+
+    for edge in graph.edge_references() {
+        writeln!(
+            writer,
+            r#"  n{} -- n{} [label="{:.3}", weight={:.6}];"#,
+            edge.source().index(),
+            edge.target().index(),
+            edge.weight(),
+            edge.weight()
+        )
+        .expect(err);
+    }
+
+    writeln!(writer, "}}").expect(err);
+
+    println!("✅  Graph exported to '{}' (DOT)", path);
+}
+
+// Escapes characters GraphML's XML text content can't contain literally.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Escapes characters a DOT quoted string can't contain literally.
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn sample_graph() -> (Graph<String, f64>, HashMap<NodeIndex, usize>) {
+        let mut graph = Graph::<String, f64>::new();
+        let a = graph.add_node("A".to_string());
+        let b = graph.add_node("B".to_string());
+        graph.add_edge(a, b, 0.987654);
+
+        let mut clusters = HashMap::new();
+        clusters.insert(a, 0);
+        clusters.insert(b, 0);
+        (graph, clusters)
+    }
+
+    #[test]
+    fn test_export_graphml_contains_nodes_edges_and_clusters() {
+        let (graph, clusters) = sample_graph();
+        let path = std::env::temp_dir().join("co2_cluster_analysis_test.graphml");
+
+        export_graphml(&graph, path.to_str().unwrap(), Some(&clusters));
+        let contents = fs::read_to_string(&path).expect("Failed to read exported GraphML file.");
+        fs::remove_file(&path).ok();
+
+        assert!(contents.contains("<graphml"));
+        assert!(contents.contains(">A<"));
+        assert!(contents.contains(">B<"));
+        assert!(contents.contains("key=\"weight\""));
+        assert!(contents.contains("key=\"cluster\""));
+    }
+
+    #[test]
+    fn test_export_dot_contains_nodes_and_weighted_edges() {
+        let (graph, clusters) = sample_graph();
+        let path = std::env::temp_dir().join("co2_cluster_analysis_test.dot");
+
+        export_dot(&graph, path.to_str().unwrap(), Some(&clusters));
+        let contents = fs::read_to_string(&path).expect("Failed to read exported DOT file.");
+        fs::remove_file(&path).ok();
+
+        assert!(contents.starts_with("graph {"));
+        assert!(contents.contains(r#"label="A""#));
+        assert!(contents.contains("cluster=0"));
+        assert!(contents.contains("weight=0.987654"));
+    }
+
+    #[test]
+    fn test_escape_xml_and_dot_special_characters() {
+        assert_eq!(escape_xml("A & B <C>"), "A &amp; B &lt;C&gt;");
+        assert_eq!(escape_dot(r#"A "quoted" \ name"#), r#"A \"quoted\" \\ name"#);
+    }
+}
@@ -1,13 +1,13 @@
 // graph.rs
 // Constructs the similarity graph, computes clusters, analyzes centrality, and saves results.
 
-use petgraph::graph::Graph;
+use petgraph::graph::{Graph, NodeIndex};
 use petgraph::algo::connected_components;
-use petgraph::unionfind::UnionFind;
 use petgraph::visit::{EdgeRef, IntoNodeReferences};
 use crate::country::CountryData;
-use crate::similarity::cosine_similarity;
-use std::collections::HashMap;
+use crate::similarity::{cosine_similarity, dot};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{Write, BufWriter};
 
@@ -41,6 +41,292 @@ pub fn build_similarity_graph(data: &HashMap<String, CountryData>, threshold: f6
     graph
 }
 
+// Builds the similarity graph like `build_similarity_graph`, but pre-filters the O(n^2)
+// pairwise comparisons with random-hyperplane SimHash LSH so only plausibly-similar pairs
+// ever reach an exact `cosine_similarity` call. Countries are hashed into a `k`-bit
+// signature (bit j = sign of the dot product with random Gaussian hyperplane j), then
+// bucketed across `l` independent tables, each keyed by a random subset of those bits; a
+// pair is only scored exactly if it collides in at least one table. Fewer bits per table
+// loosens buckets (more candidates, higher recall), and more tables (`l`) raises recall
+// further via the OR across tables. Each table keys on `k / 2` of the bits, so a larger `k`
+// tightens buckets and lowers recall while a larger `l` raises it: pick a smaller `k` and
+// larger `l` for accuracy-sensitive runs, and a larger `k` and smaller `l` when the dataset
+// is huge and approximate clustering is acceptable. Keep using `build_similarity_graph` directly for small inputs,
+// where the exact O(n^2) sweep is already cheap and LSH only adds overhead.
+// Inputs:
+//  - cleaned country emission data (vectors must already be equal-length, as clean_data guarantees)
+//  - similarity threshold
+//  - k: number of hyperplanes (signature bits)
+//  - l: number of hash tables
+// Outputs: Graph
+pub fn build_similarity_graph_lsh(
+    data: &HashMap<String, CountryData>,
+    threshold: f64,
+    k: usize,
+    l: usize,
+) -> Graph<String, f64> {
+    let mut graph = Graph::<String, f64>::new();
+    let mut nodes = HashMap::new();
+    for name in data.keys() {
+        let idx = graph.add_node(name.clone());
+        nodes.insert(name.clone(), idx);
+    }
+
+    let names: Vec<&String> = data.keys().collect();
+    if names.is_empty() || k == 0 || l == 0 {
+        return graph;
+    }
+    let dim = data[names[0]].values.len();
+    debug_assert!(
+        data.values().all(|c| c.values.len() == dim),
+        "CountryData vectors must be equal length (clean_data guarantees this)"
+    );
+
+    let mut rng = rand::thread_rng();
+    let hyperplanes: Vec<Vec<f64>> = (0..k).map(|_| gaussian_vector(&mut rng, dim)).collect();
+
+    // k-bit SimHash signature per country.
+    let signatures: HashMap<&String, Vec<bool>> = names
+        .iter()
+        .map(|&name| {
+            let values = &data[name].values;
+            let sig = hyperplanes.iter().map(|h| dot(values, h) >= 0.0).collect();
+            (name, sig)
+        })
+        .collect();
+
+    let bits_per_table = (k / 2).max(1);
+    let mut candidates: HashSet<(String, String)> = HashSet::new();
+
+    for _ in 0..l {
+        // Pick a random subset of bit positions (without replacement) as this table's key.
+        let mut bit_indices: Vec<usize> = (0..k).collect();
+        for i in 0..bits_per_table {
+            let j = rng.gen_range(i..k);
+            bit_indices.swap(i, j);
+        }
+        let chosen = &bit_indices[..bits_per_table];
+
+        let mut buckets: HashMap<Vec<bool>, Vec<&String>> = HashMap::new();
+        for &name in &names {
+            let sig = &signatures[name];
+            let key: Vec<bool> = chosen.iter().map(|&b| sig[b]).collect();
+            buckets.entry(key).or_default().push(name);
+        }
+
+        for bucket in buckets.values() {
+            for i in 0..bucket.len() {
+                for j in (i + 1)..bucket.len() {
+                    let (a, b) = (bucket[i].clone(), bucket[j].clone());
+                    let key = if a < b { (a, b) } else { (b, a) };
+                    candidates.insert(key);
+                }
+            }
+        }
+    }
+
+    for (name1, name2) in candidates {
+        let sim = cosine_similarity(&data[&name1], &data[&name2]);
+        if sim > threshold {
+            graph.add_edge(nodes[&name1], nodes[&name2], sim);
+        }
+    }
+
+    graph
+}
+
+// Draws a Gaussian-random vector via Box-Muller, used as a random hyperplane for SimHash.
+fn gaussian_vector(rng: &mut impl Rng, dim: usize) -> Vec<f64> {
+    (0..dim)
+        .map(|_| {
+            let u1: f64 = rng.gen::<f64>().max(1e-12);
+            let u2: f64 = rng.gen::<f64>();
+            (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+        })
+        .collect()
+}
+
+// Partitions the weighted similarity graph into communities by greedily maximizing
+// modularity (the standard two-phase Louvain algorithm): nodes start in singleton
+// communities and repeatedly move to whichever neighboring community most increases
+// modularity, then each resulting community is collapsed into a super-node and the
+// process recurses on the aggregated graph until no further grouping helps.
+// Inputs: reference to the similarity graph
+// Outputs: map from each original node to its final community id
+pub fn louvain_clusters(graph: &Graph<String, f64>) -> HashMap<NodeIndex, usize> {
+    let n = graph.node_count();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let mut edges: Vec<(usize, usize, f64)> = graph
+        .edge_references()
+        .filter(|e| e.source().index() != e.target().index())
+        .map(|e| (e.source().index(), e.target().index(), *e.weight()))
+        .collect();
+    let mut self_weight: Vec<f64> = vec![0.0; n];
+    for e in graph.edge_references() {
+        if e.source().index() == e.target().index() {
+            self_weight[e.source().index()] += *e.weight();
+        }
+    }
+
+    // Maps each original node to the super-node it currently belongs to.
+    let mut membership: Vec<usize> = (0..n).collect();
+
+    loop {
+        let num_nodes = self_weight.len();
+        let degree = node_degrees(num_nodes, &edges, &self_weight);
+        let m2: f64 = degree.iter().sum();
+        if m2 == 0.0 {
+            break;
+        }
+
+        let adjacency = build_adjacency(num_nodes, &edges);
+        let (community, moved) = local_move(num_nodes, &adjacency, &degree, m2);
+        let (new_n, relabel) = relabel_communities(&community);
+
+        // Fold this level's communities into the running per-node membership, through
+        // `relabel` so the ids line up with the compacted `new_n`-sized arrays the next
+        // iteration (or the aggregation below) actually uses.
+        for m in membership.iter_mut() {
+            *m = relabel[&community[*m]];
+        }
+
+        if !moved || new_n == num_nodes {
+            break;
+        }
+
+        let (new_edges, new_self_weight) = aggregate(&edges, &self_weight, &community, &relabel, new_n);
+        edges = new_edges;
+        self_weight = new_self_weight;
+    }
+
+    membership
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| (NodeIndex::new(i), c))
+        .collect()
+}
+
+// Weighted degree of every node: sum of incident edge weights plus twice any self-loop weight.
+fn node_degrees(num_nodes: usize, edges: &[(usize, usize, f64)], self_weight: &[f64]) -> Vec<f64> {
+    let mut degree = vec![0.0; num_nodes];
+    for &(a, b, w) in edges {
+        degree[a] += w;
+        degree[b] += w;
+    }
+    for (i, d) in degree.iter_mut().enumerate() {
+        *d += 2.0 * self_weight[i];
+    }
+    degree
+}
+
+// Adjacency list (neighbor, edge weight) for each node, both directions of every edge.
+fn build_adjacency(num_nodes: usize, edges: &[(usize, usize, f64)]) -> Vec<Vec<(usize, f64)>> {
+    let mut adjacency = vec![Vec::new(); num_nodes];
+    for &(a, b, w) in edges {
+        adjacency[a].push((b, w));
+        adjacency[b].push((a, w));
+    }
+    adjacency
+}
+
+// Repeatedly moves each node into the neighboring community that most increases modularity
+// (delta Q = k_i,in/m - community_tot*k_i/(2m^2), computed scaled by m as
+// k_i,in - community_tot*k_i/m2; neighbor-community terms only, since the
+// rest of Q is unchanged by the move) until no node can move. Returns the final community
+// assignment and whether any node ever moved.
+fn local_move(
+    num_nodes: usize,
+    adjacency: &[Vec<(usize, f64)>],
+    degree: &[f64],
+    m2: f64,
+) -> (Vec<usize>, bool) {
+    let mut community: Vec<usize> = (0..num_nodes).collect();
+    let mut community_tot: Vec<f64> = degree.to_vec();
+    let mut moved_ever = false;
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+        for i in 0..num_nodes {
+            let current = community[i];
+            community_tot[current] -= degree[i];
+
+            let mut neighbor_weight: HashMap<usize, f64> = HashMap::new();
+            for &(j, w) in &adjacency[i] {
+                if j != i {
+                    *neighbor_weight.entry(community[j]).or_insert(0.0) += w;
+                }
+            }
+
+            let mut best = current;
+            let mut best_gain = neighbor_weight.get(&current).copied().unwrap_or(0.0)
+                - community_tot[current] * degree[i] / m2;
+            for (&c, &k_in) in &neighbor_weight {
+                if c == current {
+                    continue;
+                }
+                let gain = k_in - community_tot[c] * degree[i] / m2;
+                if gain > best_gain {
+                    best_gain = gain;
+                    best = c;
+                }
+            }
+
+            community_tot[best] += degree[i];
+            if best != current {
+                community[i] = best;
+                moved_ever = true;
+                improved = true;
+            }
+        }
+    }
+
+    (community, moved_ever)
+}
+
+// Compacts community ids into a dense 0..k range, returning the new count and the old->new map.
+fn relabel_communities(community: &[usize]) -> (usize, HashMap<usize, usize>) {
+    let mut relabel = HashMap::new();
+    for &c in community {
+        let next = relabel.len();
+        relabel.entry(c).or_insert(next);
+    }
+    (relabel.len(), relabel)
+}
+
+// Collapses each community into a single super-node: edges internal to a community become
+// self-loop weight, and edges crossing communities are summed onto the super-node edge.
+fn aggregate(
+    edges: &[(usize, usize, f64)],
+    self_weight: &[f64],
+    community: &[usize],
+    relabel: &HashMap<usize, usize>,
+    new_n: usize,
+) -> (Vec<(usize, usize, f64)>, Vec<f64>) {
+    let mut new_self_weight = vec![0.0; new_n];
+    for (i, &c) in community.iter().enumerate() {
+        new_self_weight[relabel[&c]] += self_weight[i];
+    }
+
+    let mut new_edge_weight: HashMap<(usize, usize), f64> = HashMap::new();
+    for &(a, b, w) in edges {
+        let ca = relabel[&community[a]];
+        let cb = relabel[&community[b]];
+        if ca == cb {
+            new_self_weight[ca] += w;
+        } else {
+            let key = if ca < cb { (ca, cb) } else { (cb, ca) };
+            *new_edge_weight.entry(key).or_insert(0.0) += w;
+        }
+    }
+
+    let new_edges = new_edge_weight.into_iter().map(|((a, b), w)| (a, b, w)).collect();
+    (new_edges, new_self_weight)
+}
+
 // Prints basic stats about the graph.
 pub fn print_graph_stats(graph: &Graph<String, f64>) {
     println!("- Total Countries (Nodes): {}", graph.node_count());
@@ -66,20 +352,84 @@ pub fn print_degree_centrality(graph: &Graph<String, f64>) {
     }
 }
 
-// Groups and prints clusters (connected components).
-pub fn print_clusters(graph: &Graph<String, f64>) {
-    let mut uf = UnionFind::new(graph.node_count());
+// Computes weighted PageRank centrality over the similarity graph, treating it as
+// undirected so every edge contributes to both endpoints: each node's score spreads to
+// its neighbors in proportion to edge weight, so countries strongly similar to many
+// others outrank ones merely connected to many.
+// Inputs:
+//  - reference to the similarity graph
+//  - damping: probability mass that follows an edge each iteration (the rest resets uniformly)
+//  - iterations: maximum number of power-iteration steps
+// Outputs: (country, score) pairs, one per node, in arbitrary order
+pub fn pagerank_centrality(graph: &Graph<String, f64>, damping: f64, iterations: usize) -> Vec<(String, f64)> {
+    let n = graph.node_count();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let edges: Vec<(usize, usize, f64)> = graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index(), *e.weight()))
+        .collect();
+    let adjacency = build_adjacency(n, &edges);
+
+    let mut incident_weight = vec![0.0; n];
+    for &(a, b, w) in &edges {
+        incident_weight[a] += w;
+        incident_weight[b] += w;
+    }
+
+    let mut scores = vec![1.0 / n as f64; n];
+    let reset = (1.0 - damping) / n as f64;
 
-    // Union nodes connected by an edge
-    for edge in graph.edge_references() {
-        let (a, b) = (edge.source().index(), edge.target().index());
-        uf.union(a, b);
+    for _ in 0..iterations {
+        // Dangling (isolated) nodes have nowhere to send their mass, so it's
+        // redistributed uniformly, same as the random-reset term.
+        let dangling_mass: f64 = (0..n)
+            .filter(|&i| incident_weight[i] == 0.0)
+            .map(|i| scores[i])
+            .sum();
+
+        let mut next = vec![reset + damping * dangling_mass / n as f64; n];
+        for i in 0..n {
+            for &(j, w) in &adjacency[i] {
+                if incident_weight[j] > 0.0 {
+                    next[i] += damping * (w / incident_weight[j]) * scores[j];
+                }
+            }
+        }
+
+        let delta: f64 = scores.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < 1e-9 {
+            break;
+        }
     }
 
+    graph
+        .node_references()
+        .map(|(idx, name)| (name.clone(), scores[idx.index()]))
+        .collect()
+}
+
+// Ranks and prints countries by weighted PageRank centrality.
+pub fn print_pagerank_centrality(graph: &Graph<String, f64>, damping: f64, iterations: usize) {
+    let mut centrality = pagerank_centrality(graph, damping, iterations);
+    centrality.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    println!("🌐 Top Countries by PageRank Centrality:");
+    for (name, score) in centrality.iter().take(10) {
+        println!("- {}: {:.6}", name, score);
+    }
+}
+
+// Groups and prints clusters (modularity-maximizing communities, not just connected components).
+pub fn print_clusters(graph: &Graph<String, f64>) {
+    let communities = louvain_clusters(graph);
+
     let mut clusters: HashMap<usize, Vec<String>> = HashMap::new();
     for node_idx in graph.node_indices() {
-        let root = uf.find(node_idx.index());
-        clusters.entry(root)
+        clusters.entry(communities[&node_idx])
             .or_default()
             .push(graph[node_idx].clone());
     }
@@ -99,17 +449,11 @@ pub fn print_clusters(graph: &Graph<String, f64>) {
 // - output file name
 // Outputs: txt file written to disk 
 pub fn save_clusters_to_file(graph: &Graph<String, f64>, filename: &str) {
-    let mut uf = UnionFind::new(graph.node_count());
-
-    for edge in graph.edge_references() {
-        let (a, b) = (edge.source().index(), edge.target().index());
-        uf.union(a, b);
-    }
+    let communities = louvain_clusters(graph);
 
     let mut clusters: HashMap<usize, Vec<String>> = HashMap::new();
     for node_idx in graph.node_indices() {
-        let root = uf.find(node_idx.index());
-        clusters.entry(root)
+        clusters.entry(communities[&node_idx])
             .or_default()
             .push(graph[node_idx].clone());
     }
@@ -133,3 +477,106 @@ pub fn save_clusters_to_file(graph: &Graph<String, f64>, filename: &str) {
     println!("✅  Clusters saved to '{}'", filename);
     println!("The file contains a list of all clusters.");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Regression test for a bug where folding communities into `membership` used the
+    // unrelabeled community id instead of the compacted one, which panicked with an
+    // out-of-bounds index as soon as a community spanned more than one aggregation level.
+    #[test]
+    fn test_louvain_single_community_no_panic() {
+        let mut graph = Graph::<String, f64>::new();
+        let a = graph.add_node("A".to_string());
+        let b = graph.add_node("B".to_string());
+        let c = graph.add_node("C".to_string());
+        graph.add_edge(a, b, 0.99);
+        graph.add_edge(b, c, 0.99);
+        graph.add_edge(a, c, 0.99);
+
+        let communities = louvain_clusters(&graph);
+        assert_eq!(communities.len(), 3);
+
+        // All three nodes are mutually strongly similar, so they should land in one community.
+        let ids: std::collections::HashSet<usize> = communities.values().cloned().collect();
+        assert_eq!(ids.len(), 1, "Expected a single community, got {:?}", communities);
+    }
+
+    // Two disconnected, internally-similar pairs should end up in separate communities.
+    #[test]
+    fn test_louvain_two_communities() {
+        let mut graph = Graph::<String, f64>::new();
+        let a = graph.add_node("A".to_string());
+        let b = graph.add_node("B".to_string());
+        let c = graph.add_node("C".to_string());
+        let d = graph.add_node("D".to_string());
+        graph.add_edge(a, b, 0.99);
+        graph.add_edge(c, d, 0.99);
+
+        let communities = louvain_clusters(&graph);
+        assert_eq!(communities[&a], communities[&b]);
+        assert_eq!(communities[&c], communities[&d]);
+        assert_ne!(communities[&a], communities[&c]);
+    }
+
+    // Exercises the LSH-prefiltered graph construction end to end: identical vectors
+    // must still collide in at least one hash table and get connected, while an
+    // orthogonal vector should not.
+    #[test]
+    fn test_build_similarity_graph_lsh_connects_similar_countries() {
+        let mut countries = HashMap::new();
+        countries.insert("A".to_string(), CountryData { values: vec![1.0, 2.0, 3.0, 4.0] });
+        countries.insert("B".to_string(), CountryData { values: vec![1.0, 2.0, 3.0, 4.0] });
+        countries.insert("C".to_string(), CountryData { values: vec![4.0, -3.0, 2.0, -1.0] });
+
+        let graph = build_similarity_graph_lsh(&countries, 0.95, 16, 8);
+        assert_eq!(graph.node_count(), 3);
+
+        let a_idx = graph.node_indices().find(|idx| graph[*idx] == "A").unwrap();
+        let b_idx = graph.node_indices().find(|idx| graph[*idx] == "B").unwrap();
+        assert!(graph.find_edge(a_idx, b_idx).is_some(), "A and B should be connected.");
+        assert_eq!(graph.edge_count(), 1, "The orthogonal C should not be connected.");
+    }
+
+    // Empty input should return an empty graph, and degenerate k/l a graph with no edges, not panic.
+    #[test]
+    fn test_build_similarity_graph_lsh_empty_input() {
+        let countries: HashMap<String, CountryData> = HashMap::new();
+        let graph = build_similarity_graph_lsh(&countries, 0.95, 16, 8);
+        assert_eq!(graph.node_count(), 0);
+
+        let mut single = HashMap::new();
+        single.insert("A".to_string(), CountryData { values: vec![1.0, 2.0] });
+        let degenerate = build_similarity_graph_lsh(&single, 0.95, 0, 0);
+        assert_eq!(degenerate.node_count(), 1);
+        assert_eq!(degenerate.edge_count(), 0);
+    }
+
+    // A hub connected to two others should rank above a leaf with a single weak edge.
+    #[test]
+    fn test_pagerank_ranks_hub_above_leaf() {
+        let mut graph = Graph::<String, f64>::new();
+        let hub = graph.add_node("Hub".to_string());
+        let a = graph.add_node("A".to_string());
+        let b = graph.add_node("B".to_string());
+        let leaf = graph.add_node("Leaf".to_string());
+        graph.add_edge(hub, a, 0.99);
+        graph.add_edge(hub, b, 0.99);
+        graph.add_edge(a, leaf, 0.1);
+
+        let scores: HashMap<String, f64> = pagerank_centrality(&graph, 0.85, 100).into_iter().collect();
+        assert!(scores[graph[hub].as_str()] > scores[graph[leaf].as_str()]);
+
+        // Scores should sum to ~1.0, as with any normalized PageRank distribution.
+        let total: f64 = scores.values().sum();
+        assert!((total - 1.0).abs() < 1e-6, "Expected scores to sum to 1.0, got {}", total);
+    }
+
+    // An empty graph should yield no scores rather than dividing by zero.
+    #[test]
+    fn test_pagerank_empty_graph() {
+        let graph = Graph::<String, f64>::new();
+        assert!(pagerank_centrality(&graph, 0.85, 100).is_empty());
+    }
+}
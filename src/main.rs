@@ -5,23 +5,103 @@ mod csv_loader;
 mod country;
 mod similarity;
 mod graph;
+mod som;
+mod export;
 mod utils;
 
-// Does dataset loading, cleaning, graph construction, analysis, and saving to txt file.
+use std::env;
+
+// Does dataset loading, cleaning, then one of three clustering modes -- the exact
+// threshold graph (default), `cargo run -- lsh` for the LSH-prefiltered approximation of
+// the same graph, or `cargo run -- som` for self-organizing-map clustering -- and saves
+// results. A third argument (`graphml` or `dot`) additionally exports the graph-mode
+// result for external tools, e.g. `cargo run -- threshold graphml`.
 fn main() {
     println!(" ");
     println!("📄 Loading and cleaning data from owid-co2-data.csv...");
-    let raw_data = csv_loader::load_data("owid-co2-data.csv");
+    let raw_data = match csv_loader::load_data("owid-co2-data.csv", &csv_loader::ColumnMapping::default(), None) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
     let processed = utils::clean_data(raw_data);
     println!("✅ Successfully parsed {} countries and regions.", processed.len());
 
+    let mode = env::args().nth(1).unwrap_or_else(|| "threshold".to_string());
+    let export_format = env::args().nth(2);
+
+    match mode.as_str() {
+        "som" => run_som_clustering(&processed),
+        "lsh" => run_lsh_clustering(&processed, export_format.as_deref()),
+        _ => run_threshold_clustering(&processed, export_format.as_deref()),
+    }
+}
+
+// Builds the exact threshold similarity graph and reports clusters/centrality.
+fn run_threshold_clustering(
+    processed: &std::collections::HashMap<String, country::CountryData>,
+    export_format: Option<&str>,
+) {
     let threshold = 0.975;
     println!("🔗 Building similarity graph (threshold = {:.3})...", threshold);
-    let graph = graph::build_similarity_graph(&processed, threshold);
+    let graph = graph::build_similarity_graph(processed, threshold);
+    report_and_export(&graph, export_format);
+}
+
+// Builds the same similarity graph via SimHash LSH bucketing, trading a little recall
+// for speed on larger datasets, then reports it exactly like the threshold mode.
+fn run_lsh_clustering(
+    processed: &std::collections::HashMap<String, country::CountryData>,
+    export_format: Option<&str>,
+) {
+    let (threshold, k, l) = (0.975, 16, 8);
+    println!(
+        "🔗 Building similarity graph via LSH (threshold = {:.3}, k = {}, l = {})...",
+        threshold, k, l
+    );
+    let graph = graph::build_similarity_graph_lsh(processed, threshold, k, l);
+    report_and_export(&graph, export_format);
+}
 
+// Shared reporting/export tail for the two similarity-graph modes.
+fn report_and_export(graph: &petgraph::graph::Graph<String, f64>, export_format: Option<&str>) {
     println!("📊 Graph Statistics:");
-    graph::print_graph_stats(&graph);
-    graph::print_degree_centrality(&graph);
-    graph::print_clusters(&graph);
-    graph::save_clusters_to_file(&graph, "clusters_output.txt");
+    graph::print_graph_stats(graph);
+    graph::print_degree_centrality(graph);
+    graph::print_pagerank_centrality(graph, 0.85, 100);
+    graph::print_clusters(graph);
+    graph::save_clusters_to_file(graph, "clusters_output.txt");
+
+    if let Some(format) = export_format {
+        let clusters = graph::louvain_clusters(graph);
+        match format {
+            "graphml" => export::export_graphml(graph, "graph_output.graphml", Some(&clusters)),
+            "dot" => export::export_dot(graph, "graph_output.dot", Some(&clusters)),
+            other => println!("⚠️  Unknown export format '{}', skipping export.", other),
+        }
+    }
+}
+
+// Trains a self-organizing map and reports its cell clusters and U-matrix.
+fn run_som_clustering(processed: &std::collections::HashMap<String, country::CountryData>) {
+    let (rows, cols, epochs) = (10, 10, 100);
+    println!("🧭 Training {}x{} self-organizing map ({} epochs)...", rows, cols, epochs);
+    let map = som::Som::train(processed, rows, cols, epochs);
+
+    let clusters = som::som_clusters(&map, processed);
+    let mut cluster_list: Vec<_> = clusters.into_iter().collect();
+    cluster_list.sort_by_key(|(_, countries)| -(countries.len() as isize));
+
+    println!("\n🗺️ SOM Cell Clusters:");
+    for (cell, countries) in &cluster_list {
+        println!("- Cell {:?} ({} countries): {:?}", cell, countries.len(), &countries[..countries.len().min(5)]);
+    }
+
+    println!("\n🧊 U-Matrix (average distance to neighboring cells):");
+    for row in som::u_matrix(&map) {
+        let formatted: Vec<String> = row.iter().map(|v| format!("{:.3}", v)).collect();
+        println!("{}", formatted.join(" "));
+    }
 }
@@ -24,3 +24,25 @@ pub fn cosine_similarity(a: &CountryData, b: &CountryData) -> f64 {
         dot / (norm_a.sqrt() * norm_b.sqrt())
     }
 }
+
+// Plain dot product of two equal-length vectors.
+// Inputs: a, b: slices of the same length
+// Outputs: sum of elementwise products
+pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dot;
+
+    #[test]
+    fn test_dot_basic() {
+        assert_eq!(dot(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]), 32.0);
+    }
+
+    #[test]
+    fn test_dot_orthogonal_is_zero() {
+        assert_eq!(dot(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+    }
+}
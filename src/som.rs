@@ -0,0 +1,170 @@
+// som.rs
+// Trains a Kohonen self-organizing map over cleaned CountryData vectors, producing a
+// 2-D topological grid where countries with similar emission trajectories land on
+// nearby cells -- a softer, gradient-preserving alternative to the hard similarity
+// threshold graph in graph.rs.
+
+use crate::country::CountryData;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+
+// A trained self-organizing map: a rows x cols grid of weight vectors living in the
+// same space as the input emission vectors.
+pub struct Som {
+    rows: usize,
+    cols: usize,
+    weights: Vec<Vec<f64>>,
+}
+
+impl Som {
+    // Trains a SOM over the given country vectors.
+    // Inputs:
+    //  - cleaned country emission data
+    //  - rows, cols: grid dimensions
+    //  - epochs: number of training passes over the full dataset
+    // Outputs: trained Som
+    pub fn train(data: &HashMap<String, CountryData>, rows: usize, cols: usize, epochs: usize) -> Som {
+        let mut rng = rand::thread_rng();
+        let samples: Vec<&Vec<f64>> = data.values().map(|c| &c.values).collect();
+        if samples.is_empty() {
+            return Som { rows: 0, cols: 0, weights: Vec::new() };
+        }
+        let dim = samples[0].len();
+
+        // Initialize every cell from a random data sample rather than pure noise, so
+        // training starts already inside the data's range.
+        let mut weights: Vec<Vec<f64>> = (0..rows * cols)
+            .map(|_| (*samples.choose(&mut rng).unwrap()).clone())
+            .collect();
+
+        let initial_radius = (rows.max(cols) as f64) / 2.0;
+        let mut order = samples.clone();
+
+        for epoch in 0..epochs {
+            order.shuffle(&mut rng);
+
+            // Learning rate and neighborhood radius both decay exponentially over epochs,
+            // so early passes roughly place the grid and later passes fine-tune it.
+            let t = epoch as f64 / epochs.max(1) as f64;
+            let learning_rate = 0.5 * (-t).exp();
+            let sigma = initial_radius * (-t).exp();
+
+            for input in &order {
+                let bmu = Self::best_matching_unit(&weights, input);
+                let bmu_r = (bmu / cols) as f64;
+                let bmu_c = (bmu % cols) as f64;
+
+                for r in 0..rows {
+                    for c in 0..cols {
+                        let d2 = (r as f64 - bmu_r).powi(2) + (c as f64 - bmu_c).powi(2);
+                        let influence = (-d2 / (2.0 * sigma * sigma)).exp();
+                        if influence < 1e-6 {
+                            continue;
+                        }
+                        let idx = r * cols + c;
+                        for d in 0..dim {
+                            weights[idx][d] += learning_rate * influence * (input[d] - weights[idx][d]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Som { rows, cols, weights }
+    }
+
+    // Finds the best-matching unit: the cell whose weights are closest (Euclidean) to the input.
+    fn best_matching_unit(weights: &[Vec<f64>], input: &[f64]) -> usize {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(idx, w)| (idx, squared_distance(w, input)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(idx, _)| idx)
+            .unwrap()
+    }
+}
+
+// Squared Euclidean distance between two equal-length vectors.
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum()
+}
+
+// Assigns every country to its best-matching grid cell.
+// Inputs: trained Som, cleaned country data
+// Outputs: map from grid cell (row, col) to the countries landing there
+pub fn som_clusters(som: &Som, data: &HashMap<String, CountryData>) -> HashMap<(usize, usize), Vec<String>> {
+    let mut clusters: HashMap<(usize, usize), Vec<String>> = HashMap::new();
+    for (name, country) in data {
+        let bmu = Som::best_matching_unit(&som.weights, &country.values);
+        let cell = (bmu / som.cols, bmu % som.cols);
+        clusters.entry(cell).or_default().push(name.clone());
+    }
+    clusters
+}
+
+// Computes the U-matrix: for each cell, the average distance to its grid-adjacent
+// neighbors. High values form ridges that mark cluster boundaries the hard threshold
+// graph can't show.
+// Inputs: trained Som
+// Outputs: rows x cols grid of average neighbor distances
+pub fn u_matrix(som: &Som) -> Vec<Vec<f64>> {
+    let mut grid = vec![vec![0.0; som.cols]; som.rows];
+
+    for (r, row) in grid.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            let idx = r * som.cols + c;
+            let mut total = 0.0;
+            let mut count = 0;
+
+            for (dr, dc) in [(-1i32, 0i32), (1, 0), (0, -1), (0, 1)] {
+                let nr = r as i32 + dr;
+                let nc = c as i32 + dc;
+                if nr >= 0 && nr < som.rows as i32 && nc >= 0 && nc < som.cols as i32 {
+                    let nidx = nr as usize * som.cols + nc as usize;
+                    total += squared_distance(&som.weights[idx], &som.weights[nidx]).sqrt();
+                    count += 1;
+                }
+            }
+
+            *cell = if count > 0 { total / count as f64 } else { 0.0 };
+        }
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Training on an empty dataset must not panic on `samples[0]` and should produce a
+    // usable (empty) map rather than a degenerate one with uninitialized weights.
+    #[test]
+    fn test_train_empty_data_returns_empty_map() {
+        let data: HashMap<String, CountryData> = HashMap::new();
+        let map = Som::train(&data, 4, 4, 5);
+
+        assert!(som_clusters(&map, &data).is_empty());
+        assert!(u_matrix(&map).is_empty());
+    }
+
+    // Every country should land in exactly one cell, and the U-matrix should match the grid shape.
+    #[test]
+    fn test_train_assigns_every_country_to_a_cell() {
+        let mut data = HashMap::new();
+        data.insert("A".to_string(), CountryData { values: vec![0.0, 0.0] });
+        data.insert("B".to_string(), CountryData { values: vec![1.0, 1.0] });
+        data.insert("C".to_string(), CountryData { values: vec![0.5, 0.5] });
+
+        let map = Som::train(&data, 3, 3, 10);
+
+        let clusters = som_clusters(&map, &data);
+        let total: usize = clusters.values().map(|v| v.len()).sum();
+        assert_eq!(total, 3);
+
+        let u = u_matrix(&map);
+        assert_eq!(u.len(), 3);
+        assert!(u.iter().all(|row| row.len() == 3));
+    }
+}